@@ -0,0 +1,127 @@
+//! IO abstraction layer.
+//!
+//! On hosted targets (the default `std` feature) this is a thin re-export of `std::io`. On
+//! bare-metal targets, disabling `std` and enabling `core_io` swaps in a small vendored
+//! `Read`/`Write`/`Seek` surface instead, so [`crate::VMem`] and [`crate::VMemCursor`] work
+//! unchanged in both environments.
+//!
+//! The `core_io` crate would normally fill that bare-metal role, but its build script fails
+//! on current rustc releases (it hasn't been updated since 2021), so [`no_std_io`] vendors
+//! just the trait surface `VMem` and `VMemCursor` actually need instead of depending on it.
+//!
+//! `no_std_io` has no error-kind enum of its own, so `VMem` defines its own [`ErrorKind`]
+//! (including `AddrNotAvailable`) and converts it into the active backend's error type:
+//! `std::io::Error` on hosted targets, or `no_std_io::Error`, which simply wraps it.
+
+#[cfg(feature = "std")]
+pub use std::io::{Read, Seek, SeekFrom, Write};
+
+#[cfg(all(not(feature = "std"), feature = "core_io"))]
+pub use no_std_io::{Read, Seek, SeekFrom, Write};
+
+#[cfg(feature = "std")]
+pub type Error = std::io::Error;
+
+#[cfg(all(not(feature = "std"), feature = "core_io"))]
+pub type Error = no_std_io::Error;
+
+#[cfg(feature = "std")]
+pub type Result<T> = std::io::Result<T>;
+
+#[cfg(all(not(feature = "std"), feature = "core_io"))]
+pub type Result<T> = no_std_io::Result<T>;
+
+/// Error conditions that `VMem` itself can produce, independent of the active IO backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The requested address lies outside the `VMem`'s length.
+    AddrNotAvailable,
+    /// The input is invalid: a seek to a negative position, or snapshot bytes that are
+    /// malformed or don't fit the target `VMem`.
+    InvalidInput,
+    /// The backing [`SparseStore`](crate::SparseStore) has no room left for a new entry.
+    ///
+    /// Only ever returned by fixed-capacity stores (e.g. the `heapless` backend); the
+    /// default `BTreeMap` store never runs out of room.
+    StorageFull,
+}
+
+#[cfg(feature = "std")]
+impl From<ErrorKind> for std::io::ErrorKind {
+    fn from(kind: ErrorKind) -> Self {
+        match kind {
+            ErrorKind::AddrNotAvailable => std::io::ErrorKind::AddrNotAvailable,
+            ErrorKind::InvalidInput => std::io::ErrorKind::InvalidInput,
+            ErrorKind::StorageFull => std::io::ErrorKind::OutOfMemory,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        std::io::ErrorKind::from(kind).into()
+    }
+}
+
+#[cfg(all(not(feature = "std"), feature = "core_io"))]
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        no_std_io::Error(kind)
+    }
+}
+
+/// Vendored `Read`/`Write`/`Seek` surface for bare-metal targets, enabled by the `core_io`
+/// feature when `std` is disabled.
+///
+/// This exists only because the `core_io` crate's build script doesn't recognize current
+/// rustc releases; it implements nothing beyond what [`crate::VMem`] and
+/// [`crate::VMemCursor`] actually call.
+#[cfg(all(not(feature = "std"), feature = "core_io"))]
+mod no_std_io {
+    use super::ErrorKind;
+
+    /// The error type produced by the vendored `Read`/`Write`/`Seek` traits.
+    ///
+    /// Unlike `std::io::Error`, this carries only a [`crate::io::ErrorKind`] — there is no
+    /// underlying OS error to wrap on a bare-metal target.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Error(pub(super) ErrorKind);
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// A seek position, relative to the start, end, or the current position.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum SeekFrom {
+        Start(u64),
+        End(i64),
+        Current(i64),
+    }
+
+    pub trait Read {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+    }
+
+    pub trait Write {
+        fn write(&mut self, buf: &[u8]) -> Result<usize>;
+        fn flush(&mut self) -> Result<()>;
+    }
+
+    pub trait Seek {
+        fn seek(&mut self, pos: SeekFrom) -> Result<u64>;
+    }
+
+    impl Write for &mut [u8] {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            let n = buf.len().min(self.len());
+            let (head, tail) = core::mem::take(self).split_at_mut(n);
+            head.copy_from_slice(&buf[..n]);
+            *self = tail;
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+}
@@ -1,6 +1,10 @@
+#![no_std]
 //! `VMem` provides a virtual memory data structure where physical memory is only allocated
 //! as it is written to.
 //!
+//! This crate is `#![no_std]` (it only requires `alloc`), so `VMem` is equally at home
+//! modelling RAM inside a hosted tool and inside a bare-metal firmware image or emulator.
+//!
 //! # Example :
 //! ```
 //! use vmem::VMem;
@@ -16,24 +20,46 @@
 //!
 //! ```
 
-use std::collections::btree_map::Entry;
-use std::collections::BTreeMap;
-use std::convert::TryInto;
-use std::io::{self, ErrorKind, Write};
-use std::iter::IntoIterator;
-
-/// Virtual memory data structure.
+extern crate alloc;
+
+// The `std` feature (on by default) backs `io`'s `std::io` re-exports, and tests (which run
+// on a hosted target regardless of which IO backend is selected) pull in `std` for `dbg!`
+// and friends even though the library itself stays `no_std`.
+#[cfg(any(feature = "std", test))]
+extern crate std;
+
+mod io;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod snapshot;
+mod sparse_store;
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use core::iter::IntoIterator;
+pub use io::{ErrorKind, Read, Seek, SeekFrom, Write};
+pub use sparse_store::SparseStore;
+#[cfg(feature = "heapless")]
+pub use sparse_store::ArrayStore;
+
+/// Virtual memory data structure, generic over its backing [`SparseStore`].
+///
+/// The default store is a [`BTreeMap`], which allocates for each new word. With the
+/// `heapless` feature, [`ArrayStore`] keeps words in a fixed-capacity array and never
+/// allocates; the crate itself still links `alloc` (e.g. for [`VMem::segments`] and
+/// [`VMem::diff`]), so a global allocator is required with either store.
 #[derive(Debug, PartialEq)]
-pub struct VMem<const W: usize> {
-    memory: BTreeMap<usize, [u8; W]>,
+pub struct VMem<const W: usize, S: SparseStore<W> = BTreeMap<usize, [u8; W]>> {
+    memory: S,
     len: usize,
 }
 
-impl<const W: usize> VMem<W> {
+impl<const W: usize, S: SparseStore<W>> VMem<W, S> {
     /// Create a new [`VMem`] with a given length (the number of words).
     pub fn new(length: usize) -> Self {
         Self {
-            memory: BTreeMap::new(),
+            memory: S::default(),
             len: length,
         }
     }
@@ -43,6 +69,11 @@ impl<const W: usize> VMem<W> {
         self.len
     }
 
+    /// Whether the `VMem` spans zero words.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
     /// The width of the `VMem`
     pub fn width(&self) -> usize {
         W
@@ -63,7 +94,7 @@ impl<const W: usize> VMem<W> {
     /// Write word to the specified address.
     pub fn write_word(&mut self, word: [u8; W], addr: usize) -> Result<(), ErrorKind> {
         if addr < self.len {
-            self.memory.insert(addr, word);
+            self.memory.insert(addr, word)?;
             Ok(())
         } else {
             Err(ErrorKind::AddrNotAvailable)
@@ -107,27 +138,20 @@ impl<const W: usize> VMem<W> {
     }
 
     /// Write the content from the buffer to the `VMem` starting from the specified address.
-    pub fn write_at(&mut self, buf: &[u8], mut addr: usize) {
+    pub fn write_at(&mut self, buf: &[u8], mut addr: usize) -> Result<(), ErrorKind> {
         let mut chunk_iterator = buf.chunks_exact(W);
         for chunk in &mut chunk_iterator {
             if addr < self.len {
-                self.memory.insert(addr, chunk.try_into().unwrap());
+                self.memory.insert(addr, chunk.try_into().unwrap())?;
             }
             addr += 1;
         }
         let remainder = chunk_iterator.remainder();
         if let (false, true) = (remainder.is_empty(), addr < self.len) {
-            match self.memory.entry(addr) {
-                Entry::Vacant(block) => {
-                    let mut temp = [0x00; W];
-                    (&mut temp[..]).write(remainder).unwrap();
-                    block.insert(temp.try_into().unwrap());
-                }
-                Entry::Occupied(block) => {
-                    (&mut block.into_mut()[..]).write(remainder).unwrap();
-                }
-            }
+            let block = self.memory.get_or_insert_with(addr, || [0x00; W])?;
+            block[..remainder.len()].copy_from_slice(remainder);
         }
+        Ok(())
     }
 
     /// Diff two [`VMem`]s, returning a BTreeMap with (address, word) pairs.
@@ -151,19 +175,19 @@ impl<const W: usize> VMem<W> {
     }
 
     /// Iterate over references to the words inside the 'VMem'
-    pub fn iter<'a>(&'a self) -> Iter<'a, W> {
+    pub fn iter<'a>(&'a self) -> Iter<'a, W, S> {
         self.into_iter()
     }
 
     /// Iterate over mutable references to the words inside the 'VMem'
     ///
     ///  **Warning**: This will allocate words that hadn't been written to.
-    pub fn iter_mut<'a>(&'a mut self) -> IterMut<'a, W> {
+    pub fn iter_mut<'a>(&'a mut self) -> IterMut<'a, W, S> {
         self.into_iter()
     }
 
-    ///
-    pub fn iter_content<'a>(&'a self) -> std::collections::btree_map::Iter<'a, usize, [u8; W]> {
+    /// Iterate over the populated `(address, word)` pairs, in ascending address order.
+    pub fn iter_content<'a>(&'a self) -> S::Iter<'a> {
         self.memory.iter()
     }
 
@@ -171,17 +195,44 @@ impl<const W: usize> VMem<W> {
     pub fn chunks_adjacent_content<'a>(
         &'a self,
         chunk_size: usize,
-    ) -> ChunksAdjacentContent<'a, W> {
+    ) -> ChunksAdjacentContent<'a, W, S> {
         ChunksAdjacentContent {
             iter: self.memory.iter().peekable(),
             chunk_size,
         }
     }
+
+    /// Iterate over maximal runs of adjacent written words, each as one concatenated buffer.
+    ///
+    /// Unlike [`VMem::chunks_adjacent_content`], a [`Segment`] is never split: it spans
+    /// exactly one contiguous allocated region, however large.
+    pub fn segments<'a>(&'a self) -> Segments<'a, W, S> {
+        Segments {
+            iter: self.memory.iter().peekable(),
+        }
+    }
+
+    /// Look up the maximal run of adjacent written words covering byte address `byte_addr`.
+    ///
+    /// Returns `None` if `byte_addr` falls in an unwritten word.
+    pub fn segment_at(&self, byte_addr: usize) -> Option<Segment> {
+        self.segments().find(|segment| {
+            byte_addr >= segment.byte_offset && byte_addr < segment.byte_offset + segment.data.len()
+        })
+    }
+
+    /// Get a byte-granular [`VMemCursor`] over this `VMem`, positioned at byte `0`.
+    ///
+    /// The cursor implements [`Read`], [`Write`] and [`Seek`], so a `VMem` can be used
+    /// anywhere a standard reader/writer is expected.
+    pub fn cursor<'a>(&'a mut self) -> VMemCursor<'a, W, S> {
+        VMemCursor { vmem: self, pos: 0 }
+    }
 }
 
-impl<const W: usize> IntoIterator for VMem<W> {
+impl<const W: usize, S: SparseStore<W>> IntoIterator for VMem<W, S> {
     type Item = [u8; W];
-    type IntoIter = IntoIter<W>;
+    type IntoIter = IntoIter<W, S>;
 
     fn into_iter(self) -> Self::IntoIter {
         Self::IntoIter {
@@ -192,9 +243,9 @@ impl<const W: usize> IntoIterator for VMem<W> {
     }
 }
 
-impl<'a, const W: usize> IntoIterator for &'a VMem<W> {
+impl<'a, const W: usize, S: SparseStore<W>> IntoIterator for &'a VMem<W, S> {
     type Item = &'a [u8; W];
-    type IntoIter = Iter<'a, W>;
+    type IntoIter = Iter<'a, W, S>;
 
     fn into_iter(self) -> Self::IntoIter {
         Self::IntoIter {
@@ -205,9 +256,9 @@ impl<'a, const W: usize> IntoIterator for &'a VMem<W> {
     }
 }
 
-impl<'a, const W: usize> IntoIterator for &'a mut VMem<W> {
+impl<'a, const W: usize, S: SparseStore<W>> IntoIterator for &'a mut VMem<W, S> {
     type Item = &'a mut [u8; W];
-    type IntoIter = IterMut<'a, W>;
+    type IntoIter = IterMut<'a, W, S>;
 
     fn into_iter(self) -> Self::IntoIter {
         Self::IntoIter {
@@ -220,7 +271,7 @@ impl<'a, const W: usize> IntoIterator for &'a mut VMem<W> {
 
 impl<const W: usize> From<&[u8]> for VMem<W> {
     fn from(bytes: &[u8]) -> Self {
-        let len = bytes.len() / W + if bytes.len() % W != 0 { 1 } else { 0 };
+        let len = bytes.len() / W + if !bytes.len().is_multiple_of(W) { 1 } else { 0 };
         let mut vmem = VMem::<W>::new(len);
         for (addr, word) in bytes.chunks_exact(W).enumerate() {
             if word != [0x00; W] {
@@ -232,13 +283,13 @@ impl<const W: usize> From<&[u8]> for VMem<W> {
 }
 
 /// Iterator that takes ownership of [`VMem`].
-pub struct IntoIter<const W: usize> {
-    memory: BTreeMap<usize, [u8; W]>,
+pub struct IntoIter<const W: usize, S: SparseStore<W>> {
+    memory: S,
     length: usize,
     index: usize,
 }
 
-impl<const W: usize> Iterator for IntoIter<W> {
+impl<const W: usize, S: SparseStore<W>> Iterator for IntoIter<W, S> {
     type Item = [u8; W];
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -256,13 +307,13 @@ impl<const W: usize> Iterator for IntoIter<W> {
 }
 
 /// Iterator over a reference of [`VMem`].
-pub struct Iter<'a, const W: usize> {
-    memory: &'a BTreeMap<usize, [u8; W]>,
+pub struct Iter<'a, const W: usize, S: SparseStore<W>> {
+    memory: &'a S,
     length: usize,
     index: usize,
 }
 
-impl<'a, const W: usize> Iterator for Iter<'a, W> {
+impl<'a, const W: usize, S: SparseStore<W>> Iterator for Iter<'a, W, S> {
     type Item = &'a [u8; W];
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -280,25 +331,25 @@ impl<'a, const W: usize> Iterator for Iter<'a, W> {
 }
 
 /// Iterator over a mutable reference of [`VMem`].
-pub struct IterMut<'a, const W: usize> {
-    memory: &'a mut BTreeMap<usize, [u8; W]>,
+///
+///  **Warning**: This will allocate words that hadn't been written to; on a fixed-capacity
+///  [`SparseStore`] this panics if the store's capacity is exhausted.
+pub struct IterMut<'a, const W: usize, S: SparseStore<W>> {
+    memory: &'a mut S,
     length: usize,
     index: usize,
 }
 
-impl<'a, const W: usize> Iterator for IterMut<'a, W> {
+impl<'a, const W: usize, S: SparseStore<W>> Iterator for IterMut<'a, W, S> {
     type Item = &'a mut [u8; W];
 
     fn next(&mut self) -> Option<Self::Item> {
         let word = if self.index < self.length {
-            let entry = match self.memory.entry(self.index) {
-                Entry::Vacant(block) => {
-                    let temp = [0x00; W];
-                    &mut *block.insert(temp.try_into().unwrap())
-                }
-                Entry::Occupied(block) => block.into_mut(),
-            };
-            unsafe { Some(std::mem::transmute(&mut *entry)) }
+            let entry = self
+                .memory
+                .get_or_insert_with(self.index, || [0x00; W])
+                .expect("iter_mut exceeded the capacity of a fixed-capacity SparseStore");
+            unsafe { Some(core::mem::transmute::<&mut [u8; W], &mut [u8; W]>(&mut *entry)) }
         } else {
             None
         };
@@ -307,12 +358,13 @@ impl<'a, const W: usize> Iterator for IterMut<'a, W> {
     }
 }
 
-pub struct ChunksAdjacentContent<'a, const W: usize> {
-    iter: std::iter::Peekable<std::collections::btree_map::Iter<'a, usize, [u8; W]>>,
+/// Groups of adjacent populated words, yielded by [`VMem::chunks_adjacent_content`].
+pub struct ChunksAdjacentContent<'a, const W: usize, S: SparseStore<W> + 'a> {
+    iter: core::iter::Peekable<S::Iter<'a>>,
     chunk_size: usize,
 }
 
-impl<'a, const W: usize> Iterator for ChunksAdjacentContent<'a, W> {
+impl<'a, const W: usize, S: SparseStore<W> + 'a> Iterator for ChunksAdjacentContent<'a, W, S> {
     type Item = Vec<(&'a usize, &'a [u8; W])>;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -333,6 +385,134 @@ impl<'a, const W: usize> Iterator for ChunksAdjacentContent<'a, W> {
     }
 }
 
+/// A maximal contiguous run of written words, yielded by [`VMem::segments`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    /// The byte offset of the first word in this segment (`start_addr * W`).
+    pub byte_offset: usize,
+    /// The concatenated bytes of every word in this segment, in address order.
+    pub data: Vec<u8>,
+}
+
+/// Iterator over a [`VMem`]'s maximal runs of adjacent written words, yielded by
+/// [`VMem::segments`].
+pub struct Segments<'a, const W: usize, S: SparseStore<W> + 'a> {
+    iter: core::iter::Peekable<S::Iter<'a>>,
+}
+
+impl<'a, const W: usize, S: SparseStore<W> + 'a> Iterator for Segments<'a, W, S> {
+    type Item = Segment;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (&start_addr, first_word) = self.iter.next()?;
+        let mut data = Vec::from(&first_word[..]);
+        let mut next_addr = start_addr + 1;
+        while let Some((&addr, _)) = self.iter.peek() {
+            if addr != next_addr {
+                break;
+            }
+            let (_, word) = self.iter.next().unwrap();
+            data.extend_from_slice(&word[..]);
+            next_addr += 1;
+        }
+        Some(Segment {
+            byte_offset: start_addr * W,
+            data,
+        })
+    }
+}
+
+/// A byte-granular cursor over a [`VMem`], implementing [`Read`], [`Write`] and [`Seek`].
+///
+/// # Example
+/// ```
+/// use std::io::{Read, Write, Seek, SeekFrom};
+/// use vmem::VMem;
+///
+/// let mut vmem = VMem::<4>::new(0x10);
+/// let mut cursor = vmem.cursor();
+/// cursor.write_all(&[0x01, 0x02, 0x03, 0x04, 0x05]).unwrap();
+/// cursor.seek(SeekFrom::Start(0)).unwrap();
+///
+/// let mut buf = [0x00; 5];
+/// cursor.read_exact(&mut buf).unwrap();
+/// assert_eq!(buf, [0x01, 0x02, 0x03, 0x04, 0x05]);
+/// ```
+pub struct VMemCursor<'a, const W: usize, S: SparseStore<W>> {
+    vmem: &'a mut VMem<W, S>,
+    pos: usize,
+}
+
+impl<'a, const W: usize, S: SparseStore<W>> VMemCursor<'a, W, S> {
+    /// The length of the underlying `VMem`, in bytes.
+    fn byte_len(&self) -> usize {
+        self.vmem.len * W
+    }
+}
+
+impl<'a, const W: usize, S: SparseStore<W>> Read for VMemCursor<'a, W, S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let byte_len = self.byte_len();
+        if self.pos >= byte_len {
+            return Ok(0);
+        }
+        let n = buf.len().min(byte_len - self.pos);
+        let mut copied = 0;
+        while copied < n {
+            let addr = (self.pos + copied) / W;
+            let offset = (self.pos + copied) % W;
+            let take = (W - offset).min(n - copied);
+            let word = self.vmem.read_word(addr).unwrap();
+            buf[copied..copied + take].copy_from_slice(&word[offset..offset + take]);
+            copied += take;
+        }
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+impl<'a, const W: usize, S: SparseStore<W>> Write for VMemCursor<'a, W, S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let byte_len = self.byte_len();
+        if self.pos >= byte_len || buf.is_empty() {
+            return Ok(0);
+        }
+        let n = buf.len().min(byte_len - self.pos);
+        let mut written = 0;
+        while written < n {
+            let addr = (self.pos + written) / W;
+            let offset = (self.pos + written) % W;
+            let take = (W - offset).min(n - written);
+            let mut word = self.vmem.read_word(addr).unwrap();
+            word[offset..offset + take].copy_from_slice(&buf[written..written + take]);
+            self.vmem.write_word(word, addr).unwrap();
+            written += take;
+        }
+        self.pos += n;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, const W: usize, S: SparseStore<W>> Seek for VMemCursor<'a, W, S> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let byte_len = self.byte_len() as i64;
+        let new_pos = match pos {
+            SeekFrom::Start(offset) => offset as i64,
+            SeekFrom::End(offset) => byte_len + offset,
+            SeekFrom::Current(offset) => self.pos as i64 + offset,
+        };
+        if new_pos < 0 {
+            return Err(io::Error::from(ErrorKind::InvalidInput));
+        }
+        self.pos = new_pos as usize;
+        Ok(self.pos as u64)
+    }
+}
+
 #[test]
 fn read_word() {
     let mut vmem = VMem::<4>::new(0x0f);
@@ -367,7 +547,7 @@ fn read_at() {
         0x05
     ];
 
-    vmem.write_at(&data, 0x0d);
+    vmem.write_at(&data, 0x0d).unwrap();
     let mut buf = [0x00; 8];
     vmem.read_at(&mut buf, 0x0d).unwrap();
 
@@ -391,8 +571,8 @@ fn write_at() {
         0x05
     ];
 
-    vmem.write_at(&data, 0x0d);
-    vmem.write_at(&data, 0x02);
+    vmem.write_at(&data, 0x0d).unwrap();
+    vmem.write_at(&data, 0x02).unwrap();
     let mut buf = [0x00u8; 9];
     vmem.read_at(&mut buf, 0x2).unwrap();
 
@@ -427,8 +607,8 @@ fn into_iter() {
         0x05
     ];
 
-    vmem.write_at(&data, 0x02);
-    vmem.write_at(&data, 0x06);
+    vmem.write_at(&data, 0x02).unwrap();
+    vmem.write_at(&data, 0x06).unwrap();
 
     let mut iter = vmem.into_iter();
     assert_eq!(iter.next(), Some([0x00, 0x00, 0x00, 0x00]));
@@ -455,8 +635,8 @@ fn iter() {
         0x05
     ];
 
-    vmem.write_at(&data, 0x02);
-    vmem.write_at(&data, 0x06);
+    vmem.write_at(&data, 0x02).unwrap();
+    vmem.write_at(&data, 0x06).unwrap();
 
     let mut iter = vmem.iter();
     assert_eq!(iter.next(), Some(&[0x00, 0x00, 0x00, 0x00]));
@@ -493,7 +673,7 @@ fn iter_chunk_adjecent_content() {
     vmem.write_word([0x01, 0x02, 0x03, 0x04], 10).unwrap();
     vmem.write_word([0x02, 0x04, 0x08, 0x16], 11).unwrap();
     for chunk in vmem.chunks_adjacent_content(3) {
-        dbg!(chunk
+        std::dbg!(chunk
             .iter()
             .map(|(_, &word)| word)
             .collect::<Vec<[u8; 4]>>()
@@ -501,6 +681,136 @@ fn iter_chunk_adjecent_content() {
     }
 }
 
+#[test]
+fn iter_content_is_address_ordered() {
+    let mut vmem = VMem::<4>::new(0x10);
+    vmem.write_word([0x01, 0x02, 0x03, 0x04], 9).unwrap();
+    vmem.write_word([0x01, 0x02, 0x03, 0x04], 2).unwrap();
+    vmem.write_word([0x01, 0x02, 0x03, 0x04], 5).unwrap();
+
+    let addrs: Vec<usize> = vmem.iter_content().map(|(&addr, _)| addr).collect();
+    assert_eq!(addrs, alloc::vec![2, 5, 9]);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn array_store_write_word_and_capacity() {
+    let mut vmem = VMem::<4, ArrayStore<4, 2>>::new(0x10);
+    vmem.write_word([0x01, 0x02, 0x03, 0x04], 0x03).unwrap();
+    vmem.write_word([0x05, 0x06, 0x07, 0x08], 0x01).unwrap();
+
+    let addrs: Vec<usize> = vmem.iter_content().map(|(&addr, _)| addr).collect();
+    assert_eq!(addrs, alloc::vec![0x01, 0x03]);
+
+    let result = vmem.write_word([0x00; 4], 0x07);
+    assert_eq!(result, Err(ErrorKind::StorageFull));
+}
+
+#[test]
+fn snapshot_round_trip() {
+    let mut vmem = VMem::<4>::new(0x10);
+
+    #[rustfmt::skip]
+    let data = [
+        0x01, 0x02, 0x03, 0x04,
+        0x05, 0x06, 0x07, 0x08,
+    ];
+    vmem.write_at(&data, 0x02).unwrap();
+    vmem.write_word([0x09, 0x0a, 0x0b, 0x0c], 0x0d).unwrap();
+
+    let snapshot = vmem.to_snapshot();
+    // Proportional to written data (two runs), not to the full 0x10-word range.
+    assert!(snapshot.len() < vmem.len() * vmem.width());
+
+    let restored = VMem::<4>::from_snapshot(&snapshot).unwrap();
+    assert_eq!(vmem, restored);
+}
+
+#[test]
+fn snapshot_rejects_width_mismatch() {
+    let vmem = VMem::<4>::new(0x10);
+    let snapshot = vmem.to_snapshot();
+    let result = VMem::<8>::from_snapshot(&snapshot);
+    assert_eq!(result, Err(ErrorKind::InvalidInput));
+}
+
+#[test]
+fn snapshot_rejects_run_past_len() {
+    // `len: 4`, `width: 4`, one record: `start_addr: 2`, `run_len: 4` — extends to address 6,
+    // past the declared length of 4.
+    let bytes = [4, 4, 2, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    let result = VMem::<4>::from_snapshot(&bytes);
+    assert_eq!(result, Err(ErrorKind::InvalidInput));
+}
+
+#[test]
+fn snapshot_rejects_run_len_overflow() {
+    // `len: 4`, `width: 4`, one record whose `run_len` varint decodes to `u64::MAX`, which
+    // must be rejected via checked arithmetic rather than overflow-panicking or wrapping.
+    let mut bytes = alloc::vec![4, 4, 0];
+    snapshot::write_varint(&mut bytes, u64::MAX);
+    let result = VMem::<4>::from_snapshot(&bytes);
+    assert_eq!(result, Err(ErrorKind::InvalidInput));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip_is_sparse() {
+    let mut vmem = VMem::<4>::new(0x100);
+    vmem.write_word([0x01, 0x02, 0x03, 0x04], 0x50).unwrap();
+
+    let json = serde_json::to_string(&vmem).unwrap();
+    // The mostly-empty address range is never materialized.
+    assert!(json.len() < vmem.len() * vmem.width());
+
+    let restored: VMem<4> = serde_json::from_str(&json).unwrap();
+    assert_eq!(vmem, restored);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_rejects_out_of_bounds_address() {
+    let json = r#"{"len":4,"width":4,"memory":[[10,[1,2,3,4]]]}"#;
+    let result: Result<VMem<4>, _> = serde_json::from_str(json);
+    assert!(result.is_err());
+}
+
+#[test]
+fn segments_groups_adjacent_runs() {
+    let mut vmem = VMem::<4>::new(0x10);
+    vmem.write_word([0x01, 0x02, 0x03, 0x04], 2).unwrap();
+    vmem.write_word([0x05, 0x06, 0x07, 0x08], 3).unwrap();
+    vmem.write_word([0x09, 0x0a, 0x0b, 0x0c], 8).unwrap();
+
+    let segments: Vec<Segment> = vmem.segments().collect();
+    assert_eq!(
+        segments,
+        alloc::vec![
+            Segment {
+                byte_offset: 2 * 4,
+                data: alloc::vec![0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08],
+            },
+            Segment {
+                byte_offset: 8 * 4,
+                data: alloc::vec![0x09, 0x0a, 0x0b, 0x0c],
+            },
+        ]
+    );
+}
+
+#[test]
+fn segment_at_looks_up_covering_run() {
+    let mut vmem = VMem::<4>::new(0x10);
+    vmem.write_word([0x01, 0x02, 0x03, 0x04], 2).unwrap();
+    vmem.write_word([0x05, 0x06, 0x07, 0x08], 3).unwrap();
+
+    let segment = vmem.segment_at(2 * 4 + 5).unwrap();
+    assert_eq!(segment.byte_offset, 2 * 4);
+    assert_eq!(segment.data, alloc::vec![0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]);
+
+    assert!(vmem.segment_at(0).is_none());
+}
+
 #[test]
 fn diff() {
     let mut vmem_old = VMem::<4>::new(8);
@@ -512,10 +822,10 @@ fn diff() {
         0x05
     ];
 
-    vmem_old.write_at(&data, 0x02);
+    vmem_old.write_at(&data, 0x02).unwrap();
     let mut vmem_new = VMem::<4>::new(8);
-    vmem_new.write_at(&data, 0x02);
-    vmem_new.write_at(&data, 0x06);
+    vmem_new.write_at(&data, 0x02).unwrap();
+    vmem_new.write_at(&data, 0x06).unwrap();
 
     let patch = VMem::diff(&vmem_old, &vmem_new);
     let mut expected_patch = BTreeMap::new();
@@ -523,3 +833,50 @@ fn diff() {
     expected_patch.insert(0x07, [0x01, 0x02, 0x03, 0x04]);
     assert_eq!(patch, expected_patch);
 }
+
+#[test]
+fn cursor_read_write_across_word_boundary() {
+    let mut vmem = VMem::<4>::new(4);
+    let mut cursor = vmem.cursor();
+
+    cursor.seek(SeekFrom::Start(2)).unwrap();
+    let written = cursor.write(&[0x01, 0x02, 0x03, 0x04, 0x05]).unwrap();
+    assert_eq!(written, 5);
+
+    cursor.seek(SeekFrom::Start(0)).unwrap();
+    let mut buf = [0x00; 16];
+    let read = cursor.read(&mut buf).unwrap();
+    assert_eq!(read, 16);
+
+    #[rustfmt::skip]
+    let expected = [
+        0x00, 0x00, 0x01, 0x02,
+        0x03, 0x04, 0x05, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+    ];
+    assert_eq!(buf, expected);
+}
+
+#[test]
+fn cursor_read_at_eof_returns_zero() {
+    let mut vmem = VMem::<4>::new(2);
+    let mut cursor = vmem.cursor();
+    cursor.seek(SeekFrom::End(0)).unwrap();
+    let mut buf = [0x00; 4];
+    assert_eq!(cursor.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn cursor_seek() {
+    let mut vmem = VMem::<4>::new(4);
+    let mut cursor = vmem.cursor();
+
+    assert_eq!(cursor.seek(SeekFrom::Start(5)).unwrap(), 5);
+    assert_eq!(cursor.seek(SeekFrom::Current(-2)).unwrap(), 3);
+    assert_eq!(cursor.seek(SeekFrom::End(0)).unwrap(), 16);
+
+    cursor.seek(SeekFrom::Start(0)).unwrap();
+    let err = cursor.seek(SeekFrom::Current(-1)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
@@ -0,0 +1,61 @@
+//! Sparsity-preserving `serde` support for [`VMem`](crate::VMem), behind the `serde` feature.
+//!
+//! Only `len`, the word width, and the populated `(address, word)` entries are serialized —
+//! the full, mostly-zero address range is never materialized. Words are serialized as
+//! `Vec<u8>` rather than `[u8; W]`: `serde`'s array impls only cover a fixed list of lengths,
+//! not an arbitrary const generic `W`.
+
+use crate::{SparseStore, VMem};
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use serde::de::Error as _;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+impl<const W: usize, S: SparseStore<W>> Serialize for VMem<W, S> {
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: Serializer,
+    {
+        let entries: Vec<(usize, Vec<u8>)> = self
+            .iter_content()
+            .map(|(&addr, word)| (addr, Vec::from(&word[..])))
+            .collect();
+
+        let mut state = serializer.serialize_struct("VMem", 3)?;
+        state.serialize_field("len", &self.len())?;
+        state.serialize_field("width", &W)?;
+        state.serialize_field("memory", &entries)?;
+        state.end()
+    }
+}
+
+impl<'de, const W: usize, S: SparseStore<W>> Deserialize<'de> for VMem<W, S> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(rename = "VMem")]
+        struct Raw {
+            len: usize,
+            width: usize,
+            memory: Vec<(usize, Vec<u8>)>,
+        }
+
+        let raw = Raw::deserialize(deserializer)?;
+        if raw.width != W {
+            return Err(D::Error::custom("VMem word width does not match deserialization target"));
+        }
+
+        let mut vmem = VMem::<W, S>::new(raw.len);
+        for (addr, word) in raw.memory {
+            let word: [u8; W] = word
+                .try_into()
+                .map_err(|_| D::Error::custom("VMem word does not match the expected width"))?;
+            vmem.write_word(word, addr)
+                .map_err(|_| D::Error::custom("VMem entry address is out of bounds"))?;
+        }
+        Ok(vmem)
+    }
+}
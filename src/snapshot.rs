@@ -0,0 +1,92 @@
+//! Compact binary snapshot format for [`VMem`](crate::VMem).
+//!
+//! Unlike materializing every word via [`VMem`]'s [`Iterator`](core::iter::Iterator) impl,
+//! the snapshot format is proportional to the data actually written: a small header
+//! (`len`, word width) is followed by one record per maximal run of adjacent written words,
+//! `[start_addr: varint][run_len_in_words: varint][raw bytes]`, built on top of
+//! [`VMem::segments`]'s run-coalescing.
+
+use crate::io::ErrorKind;
+use crate::{SparseStore, VMem};
+use alloc::vec::Vec;
+
+pub(crate) fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut result: u64 = 0;
+    let mut shift = 0;
+    loop {
+        let byte = *bytes.get(*pos)?;
+        *pos += 1;
+        if shift >= 64 {
+            return None;
+        }
+        result |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(result);
+        }
+        shift += 7;
+    }
+}
+
+impl<const W: usize, S: SparseStore<W>> VMem<W, S> {
+    /// Serialize this `VMem` to the compact binary snapshot format.
+    ///
+    /// The output is proportional to the amount of written data, not to [`VMem::len`]: runs
+    /// of adjacent written words are coalesced into a single `[start_addr][run_len][bytes]`
+    /// record each.
+    pub fn to_snapshot(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_varint(&mut out, self.len() as u64);
+        write_varint(&mut out, W as u64);
+        for segment in self.segments() {
+            let start_addr = segment.byte_offset / W;
+            let run_len = segment.data.len() / W;
+            write_varint(&mut out, start_addr as u64);
+            write_varint(&mut out, run_len as u64);
+            out.extend_from_slice(&segment.data);
+        }
+        out
+    }
+
+    /// Reconstruct a `VMem` from bytes produced by [`VMem::to_snapshot`].
+    ///
+    /// This is a loader for untrusted/on-disk data: a malformed `run_len` can't overflow
+    /// `usize` arithmetic into a panic, and a run that would extend past `len` is rejected
+    /// rather than silently truncated (matching the `serde` `Deserialize` impl's behavior on
+    /// out-of-bounds addresses).
+    pub fn from_snapshot(bytes: &[u8]) -> Result<Self, ErrorKind> {
+        let mut pos = 0;
+        let len = read_varint(bytes, &mut pos).ok_or(ErrorKind::InvalidInput)? as usize;
+        let width = read_varint(bytes, &mut pos).ok_or(ErrorKind::InvalidInput)? as usize;
+        if width != W {
+            return Err(ErrorKind::InvalidInput);
+        }
+
+        let mut vmem = VMem::<W, S>::new(len);
+        while pos < bytes.len() {
+            let start_addr = read_varint(bytes, &mut pos).ok_or(ErrorKind::InvalidInput)? as usize;
+            let run_len = read_varint(bytes, &mut pos).ok_or(ErrorKind::InvalidInput)? as usize;
+            let end_addr = start_addr.checked_add(run_len).ok_or(ErrorKind::InvalidInput)?;
+            if end_addr > len {
+                return Err(ErrorKind::InvalidInput);
+            }
+            let byte_len = run_len.checked_mul(W).ok_or(ErrorKind::InvalidInput)?;
+            let end_pos = pos.checked_add(byte_len).ok_or(ErrorKind::InvalidInput)?;
+            let run_bytes = bytes.get(pos..end_pos).ok_or(ErrorKind::InvalidInput)?;
+            vmem.write_at(run_bytes, start_addr)?;
+            pos = end_pos;
+        }
+        Ok(vmem)
+    }
+}
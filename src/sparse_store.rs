@@ -0,0 +1,166 @@
+//! Pluggable sparse storage for [`VMem`](crate::VMem).
+//!
+//! `VMem` only ever needs point lookups, point inserts, and an **address-ordered**
+//! traversal of the words that have actually been written — [`SparseStore`] captures
+//! exactly that surface so the default `BTreeMap`-backed storage can be swapped for a
+//! fixed-capacity one that never allocates.
+//!
+//! The ordering guarantee on [`iter`](SparseStore::iter) is load-bearing: both
+//! [`VMem::chunks_adjacent_content`](crate::VMem::chunks_adjacent_content) and
+//! [`VMem::segments`](crate::VMem::segments) rely on entries coming out in ascending address order
+//! to detect runs of adjacent words via peeking. An implementation that yields entries out
+//! of order will silently break run detection rather than panic.
+
+use crate::io::ErrorKind;
+
+/// A sparse, address-ordered store of `W`-byte words.
+pub trait SparseStore<const W: usize>: Default {
+    /// Borrowing, address-ordered iterator over `(&address, &word)` pairs.
+    type Iter<'a>: Iterator<Item = (&'a usize, &'a [u8; W])>
+    where
+        Self: 'a;
+
+    /// Get the word at `addr`, if one has been written.
+    fn get(&self, addr: &usize) -> Option<&[u8; W]>;
+
+    /// Insert `word` at `addr`, returning the word it replaced, if any.
+    ///
+    /// Returns [`ErrorKind::StorageFull`] if the store is at capacity and `addr` was not
+    /// already occupied.
+    fn insert(&mut self, addr: usize, word: [u8; W]) -> Result<Option<[u8; W]>, ErrorKind>;
+
+    /// Get a mutable reference to the word at `addr`, inserting the result of `default` if
+    /// the slot is vacant.
+    ///
+    /// Returns [`ErrorKind::StorageFull`] if the slot is vacant and the store is at capacity.
+    fn get_or_insert_with(
+        &mut self,
+        addr: usize,
+        default: impl FnOnce() -> [u8; W],
+    ) -> Result<&mut [u8; W], ErrorKind>;
+
+    /// Address-ordered iterator over the populated words.
+    fn iter(&self) -> Self::Iter<'_>;
+}
+
+impl<const W: usize> SparseStore<W> for alloc::collections::BTreeMap<usize, [u8; W]> {
+    type Iter<'a>
+        = alloc::collections::btree_map::Iter<'a, usize, [u8; W]>
+    where
+        Self: 'a;
+
+    fn get(&self, addr: &usize) -> Option<&[u8; W]> {
+        alloc::collections::BTreeMap::get(self, addr)
+    }
+
+    fn insert(&mut self, addr: usize, word: [u8; W]) -> Result<Option<[u8; W]>, ErrorKind> {
+        Ok(alloc::collections::BTreeMap::insert(self, addr, word))
+    }
+
+    fn get_or_insert_with(
+        &mut self,
+        addr: usize,
+        default: impl FnOnce() -> [u8; W],
+    ) -> Result<&mut [u8; W], ErrorKind> {
+        use alloc::collections::btree_map::Entry;
+        Ok(match self.entry(addr) {
+            Entry::Vacant(block) => block.insert(default()),
+            Entry::Occupied(block) => block.into_mut(),
+        })
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        alloc::collections::BTreeMap::iter(self)
+    }
+}
+
+/// A fixed-capacity [`SparseStore`] that never allocates, holding up to `N`
+/// `(address, word)` entries in a sorted array. The crate itself still links `alloc`, so a
+/// global allocator must be present even when `VMem` uses this store.
+///
+/// Lookups are `O(log N)` via binary search; inserting a new address is `O(N)` to keep the
+/// array sorted. `N` should be sized to the working set the target is expected to touch.
+#[cfg(feature = "heapless")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayStore<const W: usize, const N: usize> {
+    entries: [(usize, [u8; W]); N],
+    len: usize,
+}
+
+#[cfg(feature = "heapless")]
+impl<const W: usize, const N: usize> Default for ArrayStore<W, N> {
+    fn default() -> Self {
+        Self {
+            entries: [(0, [0x00; W]); N],
+            len: 0,
+        }
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const W: usize, const N: usize> ArrayStore<W, N> {
+    fn position(&self, addr: &usize) -> Result<usize, usize> {
+        self.entries[..self.len].binary_search_by_key(addr, |(a, _)| *a)
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const W: usize, const N: usize> SparseStore<W> for ArrayStore<W, N> {
+    type Iter<'a>
+        = core::iter::Map<core::slice::Iter<'a, (usize, [u8; W])>, fn(&'a (usize, [u8; W])) -> (&'a usize, &'a [u8; W])>
+    where
+        Self: 'a;
+
+    fn get(&self, addr: &usize) -> Option<&[u8; W]> {
+        self.position(addr).ok().map(|i| &self.entries[i].1)
+    }
+
+    fn insert(&mut self, addr: usize, word: [u8; W]) -> Result<Option<[u8; W]>, ErrorKind> {
+        match self.position(&addr) {
+            Ok(i) => {
+                let previous = self.entries[i].1;
+                self.entries[i].1 = word;
+                Ok(Some(previous))
+            }
+            Err(i) => {
+                if self.len >= N {
+                    return Err(ErrorKind::StorageFull);
+                }
+                for j in (i..self.len).rev() {
+                    self.entries[j + 1] = self.entries[j];
+                }
+                self.entries[i] = (addr, word);
+                self.len += 1;
+                Ok(None)
+            }
+        }
+    }
+
+    fn get_or_insert_with(
+        &mut self,
+        addr: usize,
+        default: impl FnOnce() -> [u8; W],
+    ) -> Result<&mut [u8; W], ErrorKind> {
+        let i = match self.position(&addr) {
+            Ok(i) => i,
+            Err(i) => {
+                if self.len >= N {
+                    return Err(ErrorKind::StorageFull);
+                }
+                for j in (i..self.len).rev() {
+                    self.entries[j + 1] = self.entries[j];
+                }
+                self.entries[i] = (addr, default());
+                self.len += 1;
+                i
+            }
+        };
+        Ok(&mut self.entries[i].1)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.entries[..self.len]
+            .iter()
+            .map(|(addr, word)| (addr, word))
+    }
+}